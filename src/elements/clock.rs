@@ -2,6 +2,8 @@ use crate::objects::timestamp::{self, Datetime, Delay, Repeater, Timestamp};
 use memchr::memchr;
 
 #[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "ser", derive(serde::Serialize))]
+#[cfg_attr(feature = "de", derive(serde::Deserialize))]
 #[derive(Debug)]
 pub enum Clock<'a> {
     Closed {
@@ -137,11 +139,87 @@ impl<'a> Clock<'a> {
             },
         }
     }
+
+    /// Duration of this clock, in minutes.
+    ///
+    /// For a [`Clock::Closed`] entry, the `start`/`end` difference is
+    /// cross-checked against the stored `duration` string; on mismatch
+    /// `None` is returned rather than trusting either value. For a
+    /// [`Clock::Running`] entry this is `reference - start`, or `0` when
+    /// `reference` is `None` (e.g. no clock is running elsewhere to
+    /// supply one). A `Datetime` without a time component is treated as
+    /// midnight.
+    pub fn duration_minutes(&self, reference: Option<Datetime>) -> Option<u32> {
+        match *self {
+            Clock::Closed {
+                start,
+                end,
+                duration,
+                ..
+            } => {
+                let computed =
+                    minutes_since_epoch(&end).checked_sub(minutes_since_epoch(&start))?;
+                let computed = u32::try_from(computed).ok()?;
+                let stored = parse_duration_minutes(duration)?;
+                if computed == stored {
+                    Some(computed)
+                } else {
+                    None
+                }
+            }
+            Clock::Running { start, .. } => Some(match reference {
+                Some(reference) => {
+                    let elapsed = minutes_since_epoch(&reference) - minutes_since_epoch(&start);
+                    u32::try_from(elapsed).unwrap_or(0)
+                }
+                None => 0,
+            }),
+        }
+    }
+}
+
+/// Minutes since a fixed epoch, for ordering and subtracting `Datetime`
+/// values without pulling in a full calendar library. A time-of-day of
+/// `None` is treated as midnight.
+///
+/// Signed so that a pre-epoch `Datetime` (or a `Clock` spanning it)
+/// stays negative instead of wrapping into a huge positive value once
+/// cast to an unsigned type.
+fn minutes_since_epoch(dt: &Datetime) -> i64 {
+    let (year, month, day) = dt.date;
+    let days = days_from_civil(year as i64, month as i64, day as i64);
+    let (hour, minute) = dt.time.unwrap_or((0, 0));
+
+    days * 24 * 60 + i64::from(hour) * 60 + i64::from(minute)
+}
+
+/// Days since 1970-01-01, using Howard Hinnant's `days_from_civil`
+/// algorithm (proleptic Gregorian, valid for any `year`/`month`/`day`
+/// the parser can produce).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = (y - era * 400) as u64;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe as i64 * 365 + (yoe / 4) as i64 - (yoe / 100) as i64 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+/// Parse a `duration` string of the `CLOCK:` line's `=> H:MM` form into
+/// minutes.
+fn parse_duration_minutes(duration: &str) -> Option<u32> {
+    let colon = memchr(b':', duration.as_bytes())?;
+    let hours: u32 = duration[..colon].parse().ok()?;
+    let minutes: u32 = duration[colon + 1..].parse().ok()?;
+
+    Some(hours * 60 + minutes)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Clock;
+    use super::{minutes_since_epoch, Clock};
     use crate::objects::timestamp::Datetime;
 
     #[test]
@@ -180,4 +258,69 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn duration_minutes_running_without_reference_is_zero() {
+        let clock = Clock::Running {
+            start: Datetime {
+                date: (2020, 1, 1),
+                time: Some((9, 0)),
+            },
+            repeater: None,
+            delay: None,
+        };
+
+        assert_eq!(clock.duration_minutes(None), Some(0));
+    }
+
+    #[test]
+    fn duration_minutes_running_with_reference() {
+        let clock = Clock::Running {
+            start: Datetime {
+                date: (2020, 1, 1),
+                time: Some((9, 0)),
+            },
+            repeater: None,
+            delay: None,
+        };
+        let reference = Datetime {
+            date: (2020, 1, 1),
+            time: Some((10, 30)),
+        };
+
+        assert_eq!(clock.duration_minutes(Some(reference)), Some(90));
+    }
+
+    #[test]
+    fn duration_minutes_closed_rejects_mismatched_duration() {
+        let clock = Clock::Closed {
+            start: Datetime {
+                date: (2020, 1, 1),
+                time: Some((9, 0)),
+            },
+            end: Datetime {
+                date: (2020, 1, 1),
+                time: Some((10, 30)),
+            },
+            repeater: None,
+            delay: None,
+            duration: "2:00",
+        };
+
+        assert_eq!(clock.duration_minutes(None), None);
+    }
+
+    #[test]
+    fn minutes_since_epoch_does_not_wrap_before_1970() {
+        let before_epoch = Datetime {
+            date: (1969, 12, 31),
+            time: Some((23, 0)),
+        };
+        let epoch = Datetime {
+            date: (1970, 1, 1),
+            time: Some((0, 0)),
+        };
+
+        assert!(minutes_since_epoch(&before_epoch) < minutes_since_epoch(&epoch));
+    }
 }
\ No newline at end of file
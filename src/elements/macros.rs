@@ -11,6 +11,7 @@ use crate::elements::Element;
 
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
+#[cfg_attr(feature = "de", derive(serde::Deserialize))]
 #[derive(Debug)]
 pub struct Macros<'a> {
     pub name: Cow<'a, str>,
@@ -39,6 +40,71 @@ impl Macros<'_> {
     }
 }
 
+/// Name → template table used by [`Org::expand_macros`] to replace each
+/// [`Macros`] node with its expansion.
+///
+/// A template may reference its arguments positionally with `$1`, `$2`,
+/// … (splitting the call's comma-separated argument list) or `$0` for
+/// the whole, unsplit argument string.
+///
+/// [`Org::expand_macros`]: crate::Org::expand_macros
+#[derive(Debug, Default, Clone)]
+pub struct MacroTable<'a> {
+    templates: std::collections::HashMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> MacroTable<'a> {
+    pub fn new() -> MacroTable<'a> {
+        MacroTable::default()
+    }
+
+    /// Register (or overwrite) the template expanded for `name`.
+    pub fn insert(&mut self, name: impl Into<Cow<'a, str>>, template: impl Into<Cow<'a, str>>) {
+        self.templates.insert(name.into(), template.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Cow<'a, str>> {
+        self.templates.get(name)
+    }
+
+    /// Expand `name(arguments)` against this table, substituting `$1`,
+    /// `$2`, … with `arguments` split on commas and `$0` with
+    /// `arguments` verbatim. Returns `None` when `name` is not
+    /// registered.
+    pub fn expand(&self, name: &str, arguments: Option<&str>) -> Option<String> {
+        let template = self.get(name)?;
+        let args: Vec<&str> = arguments
+            .map(|arguments| arguments.split(',').collect())
+            .unwrap_or_default();
+
+        let mut expansion = String::with_capacity(template.len());
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                expansion.push(c);
+                continue;
+            }
+
+            match chars.peek().and_then(|c| c.to_digit(10)) {
+                Some(0) => {
+                    chars.next();
+                    expansion.push_str(arguments.unwrap_or(""));
+                }
+                Some(n) => {
+                    chars.next();
+                    if let Some(arg) = args.get(n as usize - 1) {
+                        expansion.push_str(arg.trim());
+                    }
+                }
+                None => expansion.push(c),
+            }
+        }
+
+        Some(expansion)
+    }
+}
+
 #[test]
 fn parse() {
     assert_eq!(
@@ -76,3 +142,20 @@ fn parse() {
     assert!(Macros::parse("{{{poem(}}}").is_err());
     assert!(Macros::parse("{{{poem)}}}").is_err());
 }
+
+#[test]
+fn macro_table_expand() {
+    let mut table = MacroTable::new();
+    table.insert("poem", "$1 is the color of $2");
+    table.insert("shout", "$0!!!");
+
+    assert_eq!(
+        table.expand("poem", Some("red,blue")),
+        Some("red is the color of blue".to_string())
+    );
+    assert_eq!(
+        table.expand("shout", Some("hello, world")),
+        Some("hello, world!!!".to_string())
+    );
+    assert_eq!(table.expand("missing", None), None);
+}
@@ -0,0 +1,180 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+
+use crate::elements::Clock;
+use crate::objects::timestamp::{Datetime, Timestamp};
+
+/// Timezone and reference-time context for resolving the naive,
+/// timezone-less timestamps the parser produces into concrete points in
+/// time.
+///
+/// A document never states its own timezone, and a `Running` clock or an
+/// open-ended range is only meaningful relative to "now" (or whatever
+/// moment the caller wants to evaluate it against). `EvalContext` carries
+/// both, so that timestamp arithmetic is deterministic across a whole
+/// document instead of implicitly using the system clock.
+#[derive(Debug, Clone, Copy)]
+pub struct EvalContext {
+    /// Offset every naive date/time in the document is interpreted in.
+    pub timezone: FixedOffset,
+    /// Moment used to resolve `Running` clocks and open ranges.
+    pub reference: NaiveDateTime,
+}
+
+impl EvalContext {
+    /// Build a context that evaluates `Running` clocks against
+    /// `reference`, interpreting every naive date/time in `timezone`.
+    pub fn new(timezone: FixedOffset, reference: NaiveDateTime) -> EvalContext {
+        EvalContext { timezone, reference }
+    }
+
+    fn resolve(&self, naive: NaiveDateTime) -> DateTime<FixedOffset> {
+        self.timezone
+            .from_local_datetime(&naive)
+            .single()
+            .expect("a fixed offset never produces an ambiguous local time")
+    }
+
+    fn reference_datetime(&self) -> DateTime<FixedOffset> {
+        self.resolve(self.reference)
+    }
+}
+
+/// Promote a parsed `Datetime` to a `NaiveDateTime`, treating a missing
+/// time-of-day as midnight.
+///
+/// The timestamp grammar does not reject a calendrically invalid date
+/// (e.g. `2024-02-30` or month `13`), so this returns `None` rather than
+/// asserting a guarantee the parser doesn't actually provide — mirroring
+/// `Clock::duration_minutes`, which is deliberately hand-rolled to
+/// tolerate any `year`/`month`/`day` the parser can produce.
+fn to_naive(dt: &Datetime) -> Option<NaiveDateTime> {
+    let (year, month, day) = dt.date;
+    let (hour, minute) = dt.time.unwrap_or((0, 0));
+
+    NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+        .and_then(|date| date.and_hms_opt(hour as u32, minute as u32, 0))
+}
+
+impl<'a> Timestamp<'a> {
+    /// Resolve this timestamp's start to a concrete point in time under
+    /// `ctx`. [`Timestamp::Diary`] carries no structured date and
+    /// resolves to `ctx`'s reference moment instead. Returns `None` when
+    /// the timestamp's date is not a valid calendar date.
+    pub fn to_datetime(&self, ctx: &EvalContext) -> Option<DateTime<FixedOffset>> {
+        let start = match *self {
+            Timestamp::Active { start, .. }
+            | Timestamp::ActiveRange { start, .. }
+            | Timestamp::Inactive { start, .. }
+            | Timestamp::InactiveRange { start, .. } => Some(start),
+            Timestamp::Diary(_) => None,
+        };
+
+        match start {
+            Some(start) => to_naive(&start).map(|naive| ctx.resolve(naive)),
+            None => Some(ctx.reference_datetime()),
+        }
+    }
+}
+
+impl<'a> Clock<'a> {
+    /// Elapsed time between this clock's start and either its `end` (for
+    /// a closed clock) or `ctx`'s reference moment (for a running one).
+    /// Returns `None` when either endpoint is not a valid calendar date.
+    pub fn elapsed(&self, ctx: &EvalContext) -> Option<Duration> {
+        let start = self.value().to_datetime(ctx)?;
+        let end = match self {
+            Clock::Closed { end, .. } => ctx.resolve(to_naive(end)?),
+            Clock::Running { .. } => ctx.reference_datetime(),
+        };
+
+        Some(end - start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc_ctx(reference: NaiveDateTime) -> EvalContext {
+        EvalContext::new(FixedOffset::east_opt(0).unwrap(), reference)
+    }
+
+    #[test]
+    fn reference_datetime_resolves_under_the_context_timezone() {
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let reference = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let ctx = EvalContext::new(offset, reference);
+
+        assert_eq!(ctx.reference_datetime(), offset.from_local_datetime(&reference).unwrap());
+    }
+
+    #[test]
+    fn to_datetime_resolves_a_valid_timestamp() {
+        let ctx = utc_ctx(
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        );
+        let timestamp = Timestamp::Active {
+            start: Datetime {
+                date: (2020, 6, 15),
+                time: Some((9, 30)),
+            },
+            repeater: None,
+            delay: None,
+        };
+
+        let resolved = timestamp.to_datetime(&ctx).unwrap();
+
+        assert_eq!(resolved.naive_local().to_string(), "2020-06-15 09:30:00");
+    }
+
+    #[test]
+    fn to_datetime_returns_none_for_an_invalid_calendar_date() {
+        let ctx = utc_ctx(
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        );
+        let timestamp = Timestamp::Active {
+            start: Datetime {
+                date: (2024, 2, 30),
+                time: None,
+            },
+            repeater: None,
+            delay: None,
+        };
+
+        assert_eq!(timestamp.to_datetime(&ctx), None);
+    }
+
+    #[test]
+    fn elapsed_returns_none_for_an_invalid_calendar_date() {
+        let ctx = utc_ctx(
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        );
+        let clock = Clock::Closed {
+            start: Datetime {
+                date: (2020, 1, 1),
+                time: Some((9, 0)),
+            },
+            end: Datetime {
+                date: (2020, 13, 1),
+                time: Some((9, 0)),
+            },
+            repeater: None,
+            delay: None,
+            duration: "0:00",
+        };
+
+        assert_eq!(clock.elapsed(&ctx), None);
+    }
+}
@@ -0,0 +1,186 @@
+use std::io::{Error, Write};
+
+use crate::elements::{Clock, Element, Macros};
+use crate::objects::timestamp::{Datetime, Timestamp};
+
+/// Handler trait for converting an `Org` document into Markdown.
+///
+/// Mirrors [`HtmlHandler`](crate::export::HtmlHandler) and
+/// [`OrgHandler`](crate::export::OrgHandler): [`Org::markdown_with_handler`]
+/// drives it over the same start/end [`Event`](crate::Event) stream used
+/// by the other two exporters, so callers can override individual
+/// elements while falling back to [`DefaultMarkdownHandler`] for the
+/// rest.
+///
+/// [`Org::markdown_with_handler`]: crate::Org::markdown_with_handler
+pub trait MarkdownHandler<E: From<Error>> {
+    fn start<W: Write>(&mut self, w: W, element: &Element<'_>) -> Result<(), E>;
+    fn end<W: Write>(&mut self, w: W, element: &Element<'_>) -> Result<(), E>;
+}
+
+/// Default Markdown renderer used by [`Org::markdown`](crate::Org::markdown).
+///
+/// Headlines become `#` runs, bold/italic/code emphasis become their
+/// Markdown equivalents, `Clock`/`Timestamp` elements become an inline
+/// code span, and `Macros` elements that were never expanded are
+/// rendered as an inline code placeholder rather than dropped.
+#[derive(Default)]
+pub struct DefaultMarkdownHandler;
+
+impl<E: From<Error>> MarkdownHandler<E> for DefaultMarkdownHandler {
+    fn start<W: Write>(&mut self, mut w: W, element: &Element<'_>) -> Result<(), E> {
+        match element {
+            Element::Title(title) => write!(w, "{} ", "#".repeat(title.level.min(6)))?,
+            Element::Bold => write!(w, "**")?,
+            Element::Italic => write!(w, "_")?,
+            Element::Code(value) => write!(w, "`{}`", value)?,
+            Element::Verbatim(value) => write!(w, "`{}`", value)?,
+            Element::Text(value) => write!(w, "{}", value)?,
+            Element::Clock(clock) => self.clock(w, clock)?,
+            Element::Timestamp(timestamp) => self.timestamp(w, timestamp)?,
+            Element::Macros(macros) => self.macros(w, macros)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn end<W: Write>(&mut self, mut w: W, element: &Element<'_>) -> Result<(), E> {
+        match element {
+            Element::Title(_) => writeln!(w)?,
+            Element::Bold => write!(w, "**")?,
+            Element::Italic => write!(w, "_")?,
+            Element::Paragraph => writeln!(w)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl DefaultMarkdownHandler {
+    fn clock<W: Write, E: From<Error>>(&self, mut w: W, clock: &Clock<'_>) -> Result<(), E> {
+        match clock.duration() {
+            Some(duration) => write!(w, "`CLOCK: => {}`", duration)?,
+            None => write!(w, "`CLOCK: (running)`")?,
+        }
+
+        Ok(())
+    }
+
+    fn macros<W: Write, E: From<Error>>(&self, mut w: W, macros: &Macros<'_>) -> Result<(), E> {
+        match &macros.arguments {
+            Some(arguments) => write!(w, "`{{{{{{{}({})}}}}}}`", macros.name, arguments)?,
+            None => write!(w, "`{{{{{{{}}}}}}}`", macros.name)?,
+        }
+
+        Ok(())
+    }
+
+    fn timestamp<W: Write, E: From<Error>>(
+        &self,
+        mut w: W,
+        timestamp: &Timestamp<'_>,
+    ) -> Result<(), E> {
+        write!(w, "`{}`", format_timestamp(timestamp))?;
+
+        Ok(())
+    }
+}
+
+fn format_datetime(dt: &Datetime) -> String {
+    let (year, month, day) = dt.date;
+    match dt.time {
+        Some((hour, minute)) => {
+            format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, hour, minute)
+        }
+        None => format!("{:04}-{:02}-{:02}", year, month, day),
+    }
+}
+
+fn format_timestamp(timestamp: &Timestamp<'_>) -> String {
+    match *timestamp {
+        Timestamp::Active { start, .. } | Timestamp::Inactive { start, .. } => {
+            format_datetime(&start)
+        }
+        Timestamp::ActiveRange { start, end, .. } | Timestamp::InactiveRange { start, end, .. } => {
+            format!("{}--{}", format_datetime(&start), format_datetime(&end))
+        }
+        Timestamp::Diary(ref value) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Error;
+
+    #[test]
+    fn clock_renders_as_inline_code() {
+        let mut handler = DefaultMarkdownHandler;
+        let mut buf = Vec::new();
+        let clock = Clock::Closed {
+            start: Datetime {
+                date: (2020, 1, 1),
+                time: Some((9, 0)),
+            },
+            end: Datetime {
+                date: (2020, 1, 1),
+                time: Some((10, 30)),
+            },
+            repeater: None,
+            delay: None,
+            duration: "1:30",
+        };
+        let element = Element::Clock(clock);
+
+        MarkdownHandler::<Error>::start(&mut handler, &mut buf, &element).unwrap();
+
+        assert_eq!(String::from_utf8(buf).unwrap(), "`CLOCK: => 1:30`");
+    }
+
+    #[test]
+    fn timestamp_renders_as_inline_code() {
+        let mut handler = DefaultMarkdownHandler;
+        let mut buf = Vec::new();
+        let timestamp = Timestamp::Active {
+            start: Datetime {
+                date: (2020, 1, 2),
+                time: None,
+            },
+            repeater: None,
+            delay: None,
+        };
+        let element = Element::Timestamp(timestamp);
+
+        MarkdownHandler::<Error>::start(&mut handler, &mut buf, &element).unwrap();
+
+        assert_eq!(String::from_utf8(buf).unwrap(), "`2020-01-02`");
+    }
+
+    #[test]
+    fn timestamp_range_renders_as_inline_code() {
+        let mut handler = DefaultMarkdownHandler;
+        let mut buf = Vec::new();
+        let timestamp = Timestamp::InactiveRange {
+            start: Datetime {
+                date: (2020, 1, 1),
+                time: Some((9, 0)),
+            },
+            end: Datetime {
+                date: (2020, 1, 1),
+                time: Some((10, 30)),
+            },
+            repeater: None,
+            delay: None,
+        };
+        let element = Element::Timestamp(timestamp);
+
+        MarkdownHandler::<Error>::start(&mut handler, &mut buf, &element).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "`2020-01-01 09:00--2020-01-01 10:30`"
+        );
+    }
+}
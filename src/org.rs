@@ -1,11 +1,18 @@
 use indextree::{Arena, NodeEdge, NodeId};
+use std::collections::HashMap;
 use std::io::{Error, Write};
 use std::ops::{Index, IndexMut};
+use std::time::Duration;
 
 use crate::{
     config::{ParseConfig, DEFAULT_CONFIG},
-    elements::Element,
-    export::{DefaultHtmlHandler, DefaultOrgHandler, HtmlHandler, OrgHandler},
+    elements::{Element, MacroTable, Macros},
+    eval_context::EvalContext,
+    export::{
+        DefaultHtmlHandler, DefaultMarkdownHandler, DefaultOrgHandler, HtmlHandler,
+        MarkdownHandler, OrgHandler,
+    },
+    objects::timestamp::Datetime,
     parsers::{blank_lines, parse_container, Container},
 };
 
@@ -14,6 +21,16 @@ pub struct Org<'a> {
     pub(crate) root: NodeId,
 }
 
+/// Re-serialize a macro name/arguments pair back into `{{{name(args)}}}`
+/// form, so a node's own call enters the same parse-and-expand loop as
+/// the text its expansions produce.
+fn macro_call(name: &str, arguments: Option<&str>) -> String {
+    match arguments {
+        Some(arguments) => format!("{{{{{{{}({})}}}}}}", name, arguments),
+        None => format!("{{{{{{{}}}}}}}", name),
+    }
+}
+
 #[derive(Debug)]
 pub enum Event<'a, 'b> {
     Start(&'b Element<'a>),
@@ -117,6 +134,172 @@ impl<'a> Org<'a> {
 
         Ok(())
     }
+
+    pub fn markdown<W>(&self, writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.markdown_with_handler(writer, &mut DefaultMarkdownHandler)
+    }
+
+    pub fn markdown_with_handler<W, H, E>(&self, mut writer: W, handler: &mut H) -> Result<(), E>
+    where
+        W: Write,
+        E: From<Error>,
+        H: MarkdownHandler<E>,
+    {
+        for event in self.iter() {
+            match event {
+                Event::Start(element) => handler.start(&mut writer, element)?,
+                Event::End(element) => handler.end(&mut writer, element)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scan every `#+MACRO: name template` keyword in the document into
+    /// a [`MacroTable`].
+    pub fn macro_table(&self) -> MacroTable<'static> {
+        let mut table = MacroTable::new();
+
+        for node in self.root.descendants(&self.arena) {
+            if let Element::Keyword(keyword) = &self[node] {
+                if keyword.key.eq_ignore_ascii_case("MACRO") {
+                    if let Some((name, template)) = keyword.value.split_once(char::is_whitespace) {
+                        table.insert(name.trim().to_string(), template.trim().to_string());
+                    }
+                }
+            }
+        }
+
+        table
+    }
+
+    /// Expand every [`Element::Macros`] node against `table`, replacing
+    /// it in place with an [`Element::Text`] node holding its expansion.
+    /// The expansion is not parsed as Org markup, and a name with no
+    /// expansion keeps its literal `{{{name(args)}}}` text.
+    ///
+    /// Falls back to the built-ins `{{{title}}}`/`{{{author}}}` (pulled
+    /// from the document's own keywords) and `{{{time(FORMAT)}}}`
+    /// (resolved against `ctx`'s reference moment) when `table` has no
+    /// entry for a name. When the *produced text* is itself exactly one
+    /// `{{{name(args)}}}` call, it is re-parsed and expanded again, up to
+    /// a small recursion guard — this is what bounds a macro that
+    /// (directly or indirectly) references itself. A call embedded in
+    /// other text is left as written.
+    pub fn expand_macros(&mut self, table: &MacroTable<'_>, ctx: &EvalContext) {
+        const MAX_EXPANSIONS: usize = 8;
+
+        let nodes: Vec<NodeId> = self.root.descendants(&self.arena).collect();
+
+        for node in nodes {
+            let (name, arguments) = match &self[node] {
+                Element::Macros(Macros { name, arguments }) => {
+                    (name.to_string(), arguments.as_ref().map(|a| a.to_string()))
+                }
+                _ => continue,
+            };
+
+            let mut text = macro_call(&name, arguments.as_deref());
+
+            for _ in 0..MAX_EXPANSIONS {
+                let (name, arguments) = match Macros::parse(&text) {
+                    Ok(("", Element::Macros(Macros { name, arguments }))) => {
+                        (name.to_string(), arguments.as_ref().map(|a| a.to_string()))
+                    }
+                    _ => break,
+                };
+
+                let expansion = table
+                    .expand(&name, arguments.as_deref())
+                    .or_else(|| self.expand_builtin_macro(&name, arguments.as_deref(), ctx));
+
+                match expansion {
+                    Some(expansion) => text = expansion,
+                    None => break,
+                }
+            }
+
+            self[node] = Element::Text(text.into());
+        }
+    }
+
+    fn expand_builtin_macro(
+        &self,
+        name: &str,
+        arguments: Option<&str>,
+        ctx: &EvalContext,
+    ) -> Option<String> {
+        match name {
+            "title" => self.document_keyword("TITLE"),
+            "author" => self.document_keyword("AUTHOR"),
+            "time" => {
+                let format = arguments.unwrap_or("%Y-%m-%d %H:%M");
+                Some(ctx.reference.format(format).to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn document_keyword(&self, key: &str) -> Option<String> {
+        self.root.descendants(&self.arena).find_map(|node| match &self[node] {
+            Element::Keyword(keyword) if keyword.key.eq_ignore_ascii_case(key) => {
+                Some(keyword.value.to_string())
+            }
+            _ => None,
+        })
+    }
+
+    /// Roll every `Clock` element up into a per-headline time-tracking
+    /// report.
+    ///
+    /// `reference` is the point in time used to evaluate any `Running`
+    /// clock (pass `None` to treat an unreferenced running clock as
+    /// contributing zero, rather than panicking). Each node's total
+    /// includes its own clocks plus those of every descendant, so a
+    /// headline's entry already accounts for its sub-headlines.
+    pub fn clock_report(&self, reference: Option<Datetime>) -> ClockReport {
+        let mut totals: HashMap<NodeId, Duration> = HashMap::new();
+        let mut grand_total = Duration::default();
+
+        for node in self.root.descendants(&self.arena) {
+            let clock = match &self[node] {
+                Element::Clock(clock) => clock,
+                _ => continue,
+            };
+
+            let minutes = match clock.duration_minutes(reference) {
+                Some(minutes) => minutes,
+                None => continue,
+            };
+            let duration = Duration::from_secs(u64::from(minutes) * 60);
+
+            grand_total += duration;
+
+            let mut ancestor = self.arena[node].parent();
+            while let Some(id) = ancestor {
+                *totals.entry(id).or_insert_with(Duration::default) += duration;
+                ancestor = self.arena[id].parent();
+            }
+        }
+
+        ClockReport {
+            totals,
+            grand_total,
+        }
+    }
+}
+
+/// Per-headline time-tracking report produced by [`Org::clock_report`].
+#[derive(Debug, Default)]
+pub struct ClockReport {
+    /// Minutes accumulated under each node: its own clocks plus those of
+    /// every descendant.
+    pub totals: HashMap<NodeId, Duration>,
+    /// Sum of every clock duration in the document.
+    pub grand_total: Duration,
 }
 
 impl Default for Org<'static> {
@@ -150,3 +333,264 @@ impl Serialize for Org<'_> {
         serializer.serialize_newtype_struct("Org", &Node::new(self.root, &self.arena))
     }
 }
+
+#[cfg(feature = "de")]
+use serde::{de::Deserializer, Deserialize};
+
+/// Mirrors the shape [`Node`](serde_indextree::Node) serializes: a node's
+/// own data plus its children, in document order. This is what `Org`'s
+/// `Deserialize` impl reads before re-linking everything into an arena.
+///
+/// `Element<'static>: Deserialize` requires every variant payload to
+/// derive it too, the same way the `ser` feature already requires each
+/// to derive `Serialize` — see `Macros` and `Clock`.
+#[cfg(feature = "de")]
+#[derive(Deserialize)]
+struct OrgNode {
+    data: Element<'static>,
+    #[serde(default)]
+    children: Vec<OrgNode>,
+}
+
+#[cfg(feature = "de")]
+impl OrgNode {
+    fn into_arena(self, arena: &mut Arena<Element<'static>>) -> NodeId {
+        let id = arena.new_node(self.data);
+
+        for child in self.children {
+            let child_id = child.into_arena(arena);
+            id.append(child_id, arena);
+        }
+
+        id
+    }
+}
+
+/// Rebuilds the `indextree::Arena` an `Org` was serialized from, so a
+/// document can be cached or shipped across processes (e.g. as
+/// MessagePack) without re-parsing its source text.
+#[cfg(feature = "de")]
+impl<'de> Deserialize<'de> for Org<'static> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let root_node = OrgNode::deserialize(deserializer)?;
+
+        let mut arena = Arena::new();
+        let root = root_node.into_arena(&mut arena);
+        let org = Org { arena, root };
+
+        org.debug_validate();
+
+        Ok(org)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::{Clock, Keyword};
+    use crate::objects::timestamp::Datetime;
+    use chrono::{FixedOffset, NaiveDate};
+
+    #[test]
+    fn clock_report_rolls_up_to_ancestors() {
+        let mut org = Org::new();
+        let headline = org.arena.new_node(Element::Document { pre_blank: 0 });
+        org.root.append(headline, &mut org.arena);
+
+        let clock = Clock::Closed {
+            start: Datetime {
+                date: (2020, 1, 1),
+                time: Some((9, 0)),
+            },
+            end: Datetime {
+                date: (2020, 1, 1),
+                time: Some((10, 30)),
+            },
+            repeater: None,
+            delay: None,
+            duration: "1:30",
+        };
+        let clock_node = org.arena.new_node(Element::Clock(clock));
+        headline.append(clock_node, &mut org.arena);
+
+        let report = org.clock_report(None);
+        let expected = Duration::from_secs(90 * 60);
+
+        assert_eq!(report.totals[&headline], expected);
+        assert_eq!(report.grand_total, expected);
+    }
+
+    #[test]
+    fn expand_macros_follows_a_chain() {
+        let mut org = Org::new();
+        let node = org
+            .arena
+            .new_node(Element::Macros(Macros {
+                name: "a".into(),
+                arguments: None,
+            }));
+        org.root.append(node, &mut org.arena);
+
+        let mut table = MacroTable::new();
+        table.insert("a", "{{{b}}}");
+        table.insert("b", "resolved");
+
+        let ctx = EvalContext::new(
+            FixedOffset::east_opt(0).unwrap(),
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        );
+
+        org.expand_macros(&table, &ctx);
+
+        match &org[node] {
+            Element::Text(text) => assert_eq!(text.as_ref(), "resolved"),
+            other => panic!("expected expanded text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn expand_macros_terminates_on_self_reference() {
+        let mut org = Org::new();
+        let node = org
+            .arena
+            .new_node(Element::Macros(Macros {
+                name: "a".into(),
+                arguments: None,
+            }));
+        org.root.append(node, &mut org.arena);
+
+        let mut table = MacroTable::new();
+        table.insert("a", "{{{a}}}");
+
+        let ctx = EvalContext::new(
+            FixedOffset::east_opt(0).unwrap(),
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        );
+
+        // Must terminate rather than loop forever; the exact leftover
+        // text is whatever the guard's last expansion produced.
+        org.expand_macros(&table, &ctx);
+
+        match &org[node] {
+            Element::Text(text) => assert_eq!(text.as_ref(), "{{{a}}}"),
+            other => panic!("expected leftover macro text, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "de")]
+    #[test]
+    fn org_node_rebuilds_arena_in_document_order() {
+        let tree = OrgNode {
+            data: Element::Document { pre_blank: 0 },
+            children: vec![
+                OrgNode {
+                    data: Element::Document { pre_blank: 1 },
+                    children: vec![],
+                },
+                OrgNode {
+                    data: Element::Document { pre_blank: 2 },
+                    children: vec![],
+                },
+            ],
+        };
+
+        let mut arena = Arena::new();
+        let root = tree.into_arena(&mut arena);
+        let org = Org { arena, root };
+
+        org.debug_validate();
+        let order: Vec<_> = org
+            .root
+            .children(&org.arena)
+            .map(|child| match &org[child] {
+                Element::Document { pre_blank } => *pre_blank,
+                other => panic!("expected Document child, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(order, vec![1, 2]);
+    }
+
+    #[cfg(all(feature = "ser", feature = "de"))]
+    #[test]
+    fn org_round_trips_through_serde_json() {
+        let mut org = Org::new();
+        let child = org.arena.new_node(Element::Document { pre_blank: 2 });
+        org.root.append(child, &mut org.arena);
+
+        let json = serde_json::to_string(&org).unwrap();
+        let restored: Org<'static> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.root.children(&restored.arena).count(), 1);
+        match &restored[restored.root] {
+            Element::Document { pre_blank } => assert_eq!(*pre_blank, 0),
+            other => panic!("expected root Document, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn macro_table_and_builtins_resolve_from_document_keywords() {
+        let mut org = Org::new();
+
+        let title_kw = org.arena.new_node(Element::Keyword(Keyword {
+            key: "TITLE".into(),
+            value: "My Document".into(),
+        }));
+        org.root.append(title_kw, &mut org.arena);
+
+        let macro_kw = org.arena.new_node(Element::Keyword(Keyword {
+            key: "MACRO".into(),
+            value: "greet Hello, $1!".into(),
+        }));
+        org.root.append(macro_kw, &mut org.arena);
+
+        let title_node = org.arena.new_node(Element::Macros(Macros {
+            name: "title".into(),
+            arguments: None,
+        }));
+        org.root.append(title_node, &mut org.arena);
+
+        let greet_node = org.arena.new_node(Element::Macros(Macros {
+            name: "greet".into(),
+            arguments: Some("world".into()),
+        }));
+        org.root.append(greet_node, &mut org.arena);
+
+        let time_node = org.arena.new_node(Element::Macros(Macros {
+            name: "time".into(),
+            arguments: Some("%Y-%m-%d".into()),
+        }));
+        org.root.append(time_node, &mut org.arena);
+
+        let table = org.macro_table();
+        assert_eq!(table.get("greet").map(|t| t.as_ref()), Some("Hello, $1!"));
+
+        let ctx = EvalContext::new(
+            FixedOffset::east_opt(0).unwrap(),
+            NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        );
+
+        org.expand_macros(&table, &ctx);
+
+        match &org[title_node] {
+            Element::Text(text) => assert_eq!(text.as_ref(), "My Document"),
+            other => panic!("expected expanded title, got {:?}", other),
+        }
+        match &org[greet_node] {
+            Element::Text(text) => assert_eq!(text.as_ref(), "Hello, world!"),
+            other => panic!("expected expanded greet, got {:?}", other),
+        }
+        match &org[time_node] {
+            Element::Text(text) => assert_eq!(text.as_ref(), "2020-01-01"),
+            other => panic!("expected expanded time, got {:?}", other),
+        }
+    }
+}